@@ -0,0 +1,148 @@
+// Recursive asset-folder import.
+//
+// `scan_assets_dir` walks a directory the user picked (e.g. a tileset
+// folder) and lists the image files it finds without touching anything.
+// `import_scanned_assets` then copies whichever of those the user selected
+// into the app data dir and registers them in the "assets" store, so a
+// whole folder can be registered in one action instead of one image at a
+// time through `write_imported_assets`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use walkdir::WalkDir;
+
+use crate::scope::ScopeManager;
+use crate::store;
+
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "webp", "bmp"];
+
+#[derive(Clone, Serialize)]
+pub struct ScannedAsset {
+    path: String,
+    file_name: String,
+    size: u64,
+    modified: u64,
+}
+
+fn is_image_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// Derives a destination file name from a hash of the canonicalized source
+// path so that same-named files from different subfolders (e.g.
+// floors/stone.png and walls/stone.png) don't collide when copied into the
+// flat assets dir or overwrite each other's store entry.
+fn unique_dest_name(canonical_source: &std::path::Path) -> Result<String, String> {
+    let file_name = canonical_source
+        .file_name()
+        .ok_or_else(|| format!("Invalid asset path: {}", canonical_source.display()))?
+        .to_string_lossy()
+        .into_owned();
+    let mut hasher = DefaultHasher::new();
+    canonical_source.hash(&mut hasher);
+    Ok(format!("{:016x}_{}", hasher.finish(), file_name))
+}
+
+// Command to recursively list image files under a directory
+#[tauri::command]
+pub async fn scan_assets_dir(
+    scope: tauri::State<'_, ScopeManager>,
+    path: String,
+) -> Result<Vec<ScannedAsset>, String> {
+    let root = scope.check_dir(std::path::Path::new(&path))?;
+    let mut found = Vec::new();
+
+    for entry in WalkDir::new(&root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        if !entry.file_type().is_file() || !is_image_file(entry.path()) {
+            continue;
+        }
+
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0);
+
+        let absolute_path = entry
+            .path()
+            .canonicalize()
+            .unwrap_or_else(|_| entry.path().to_path_buf());
+
+        found.push(ScannedAsset {
+            path: absolute_path.to_string_lossy().into_owned(),
+            file_name: entry.file_name().to_string_lossy().into_owned(),
+            size: metadata.len(),
+            modified,
+        });
+    }
+
+    Ok(found)
+}
+
+// Command to copy selected scanned assets into the app data dir and
+// register them in the imported-assets store
+#[tauri::command]
+pub async fn import_scanned_assets(
+    app_handle: AppHandle,
+    scope: tauri::State<'_, ScopeManager>,
+    paths: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    let assets_dir = app_data_dir.join("assets");
+    std::fs::create_dir_all(&assets_dir)
+        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
+
+    let existing = store::get(&app_handle, "assets", "list")?.unwrap_or_else(|| json!([]));
+    let mut list = match existing {
+        serde_json::Value::Array(entries) => entries,
+        _ => Vec::new(),
+    };
+
+    let mut imported_paths = Vec::new();
+
+    for source in paths {
+        let source_path = scope.check(std::path::Path::new(&source))?;
+        let file_name = source_path
+            .file_name()
+            .ok_or_else(|| format!("Invalid asset path: {}", source))?
+            .to_string_lossy()
+            .into_owned();
+        let dest_name = unique_dest_name(&source_path)?;
+        let dest_path = assets_dir.join(&dest_name);
+
+        std::fs::copy(&source_path, &dest_path)
+            .map_err(|e| format!("Failed to import {}: {}", source, e))?;
+
+        let dest_path_str = dest_path.to_string_lossy().into_owned();
+        list.push(json!({
+            "id": dest_name,
+            "fileName": file_name,
+            "sourcePath": source,
+            "importedPath": dest_path_str,
+        }));
+        imported_paths.push(dest_path_str);
+    }
+
+    store::set(&app_handle, "assets", "list", serde_json::Value::Array(list))?;
+    Ok(imported_paths)
+}
@@ -0,0 +1,70 @@
+// Binary-safe file I/O.
+//
+// `read_file` historically used `read_to_string`, so it errored on any
+// binary asset (PNG tilesets, etc.) and on text files containing invalid
+// UTF-8. These commands let the frontend move raw bytes as base64 instead,
+// and report the detected MIME type/extension alongside the data so an
+// imported image can be turned straight into a data URL.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::Serialize;
+
+use crate::scope::ScopeManager;
+
+#[derive(Serialize)]
+pub struct BinaryFile {
+    data: String,
+    mime_type: String,
+    extension: String,
+}
+
+fn guess_mime_type(extension: &str) -> &'static str {
+    match extension.to_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "json" => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+// Command to read a file's raw bytes as base64, with its detected MIME type
+#[tauri::command]
+pub async fn read_file_base64(
+    scope: tauri::State<'_, ScopeManager>,
+    file_path: String,
+) -> Result<BinaryFile, String> {
+    let allowed_path = scope.check(std::path::Path::new(&file_path))?;
+    let bytes =
+        std::fs::read(&allowed_path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let extension = allowed_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    Ok(BinaryFile {
+        data: BASE64.encode(bytes),
+        mime_type: guess_mime_type(&extension).to_string(),
+        extension,
+    })
+}
+
+// Command to write base64-encoded bytes to a file
+#[tauri::command]
+pub async fn write_file_base64(
+    scope: tauri::State<'_, ScopeManager>,
+    file_path: String,
+    data: String,
+) -> Result<(), String> {
+    let allowed_path = scope.check(std::path::Path::new(&file_path))?;
+    let bytes = BASE64
+        .decode(data)
+        .map_err(|e| format!("Failed to decode base64 data: {}", e))?;
+    std::fs::write(&allowed_path, bytes).map_err(|e| format!("Failed to write file: {}", e))
+}
@@ -1,26 +1,44 @@
+mod assets;
+mod binary;
+mod map_backup;
+mod scope;
+mod store;
+
+use scope::ScopeManager;
+
 // Command to show open file dialog and return the selected file path
 #[tauri::command]
-async fn open_file_dialog(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn open_file_dialog(
+    app_handle: tauri::AppHandle,
+    scope: tauri::State<'_, ScopeManager>,
+) -> Result<Option<String>, String> {
     use tauri_plugin_dialog::DialogExt;
-    
+
     let file_path = app_handle
         .dialog()
         .file()
         .add_filter("JSON Files", &["json"])
         .add_filter("All Files", &["*"])
         .blocking_pick_file();
-        
+
     match file_path {
-        Some(path) => Ok(Some(path.to_string())),
+        Some(path) => {
+            let path_str = path.to_string();
+            scope.grant(std::path::Path::new(&path_str))?;
+            Ok(Some(path_str))
+        }
         None => Ok(None),
     }
 }
 
 // Command to show save file dialog and return the selected file path
 #[tauri::command]
-async fn save_file_dialog(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn save_file_dialog(
+    app_handle: tauri::AppHandle,
+    scope: tauri::State<'_, ScopeManager>,
+) -> Result<Option<String>, String> {
     use tauri_plugin_dialog::DialogExt;
-    
+
     let file_path = app_handle
         .dialog()
         .file()
@@ -28,26 +46,68 @@ async fn save_file_dialog(app_handle: tauri::AppHandle) -> Result<Option<String>
         .add_filter("All Files", &["*"])
         .set_file_name("dungeon_map.json")
         .blocking_save_file();
-        
+
     match file_path {
-        Some(path) => Ok(Some(path.to_string())),
+        Some(path) => {
+            let path_str = path.to_string();
+            scope.grant(std::path::Path::new(&path_str))?;
+            Ok(Some(path_str))
+        }
         None => Ok(None),
     }
 }
 
+// Command to show a multi-select open file dialog and return the selected file paths
+#[tauri::command]
+async fn open_files_dialog(
+    app_handle: tauri::AppHandle,
+    scope: tauri::State<'_, ScopeManager>,
+) -> Result<Vec<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let file_paths = app_handle
+        .dialog()
+        .file()
+        .add_filter("JSON Files", &["json"])
+        .add_filter("All Files", &["*"])
+        .blocking_pick_files();
+
+    match file_paths {
+        Some(paths) => {
+            let mut result = Vec::with_capacity(paths.len());
+            for path in paths {
+                let path_str = path.to_string();
+                scope.grant(std::path::Path::new(&path_str))?;
+                result.push(path_str);
+            }
+            Ok(result)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
 // Command to read file contents
 #[tauri::command]
-async fn read_file(file_path: String) -> Result<String, String> {
-    match std::fs::read_to_string(&file_path) {
-        Ok(contents) => Ok(contents),
+async fn read_file(
+    scope: tauri::State<'_, ScopeManager>,
+    file_path: String,
+) -> Result<String, String> {
+    let allowed_path = scope.check(std::path::Path::new(&file_path))?;
+    match std::fs::read(&allowed_path) {
+        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
         Err(e) => Err(format!("Failed to read file: {}", e)),
     }
 }
 
 // Command to write file contents
 #[tauri::command]
-async fn write_file(file_path: String, contents: String) -> Result<(), String> {
-    match std::fs::write(&file_path, contents) {
+async fn write_file(
+    scope: tauri::State<'_, ScopeManager>,
+    file_path: String,
+    contents: String,
+) -> Result<(), String> {
+    let allowed_path = scope.check(std::path::Path::new(&file_path))?;
+    match std::fs::write(&allowed_path, contents) {
         Ok(_) => Ok(()),
         Err(e) => Err(format!("Failed to write file: {}", e)),
     }
@@ -55,14 +115,17 @@ async fn write_file(file_path: String, contents: String) -> Result<(), String> {
 
 // Command to load map data from file
 #[tauri::command]
-async fn load_map(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn load_map(
+    app_handle: tauri::AppHandle,
+    scope: tauri::State<'_, ScopeManager>,
+) -> Result<Option<String>, String> {
     // First, show the open file dialog
-    let file_path_opt = open_file_dialog(app_handle).await?;
-    
+    let file_path_opt = open_file_dialog(app_handle, scope.clone()).await?;
+
     match file_path_opt {
         Some(file_path) => {
             // Read the file contents
-            match read_file(file_path).await {
+            match read_file(scope, file_path).await {
                 Ok(contents) => Ok(Some(contents)),
                 Err(e) => Err(e),
             }
@@ -71,150 +134,108 @@ async fn load_map(app_handle: tauri::AppHandle) -> Result<Option<String>, String
     }
 }
 
+// Command to load several map files at once, keyed by their path
+#[tauri::command]
+async fn load_maps(
+    scope: tauri::State<'_, ScopeManager>,
+    paths: Vec<String>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let mut contents = std::collections::HashMap::with_capacity(paths.len());
+    for path in paths {
+        let data = read_file(scope.clone(), path.clone()).await?;
+        contents.insert(path, data);
+    }
+    Ok(contents)
+}
+
 // Command to save map data to file
 #[tauri::command]
-async fn save_map(app_handle: tauri::AppHandle, map_data: String) -> Result<bool, String> {
+async fn save_map(
+    app_handle: tauri::AppHandle,
+    scope: tauri::State<'_, ScopeManager>,
+    map_data: String,
+) -> Result<bool, String> {
     // First, show the save file dialog
-    let file_path_opt = save_file_dialog(app_handle).await?;
-    
+    let file_path_opt = save_file_dialog(app_handle, scope).await?;
+
     match file_path_opt {
         Some(file_path) => {
-            // Write the file contents
-            match write_file(file_path, map_data).await {
-                Ok(_) => Ok(true),
-                Err(e) => Err(e),
-            }
+            // Write the file contents atomically, rotating the previous version into a backup
+            map_backup::atomic_write_with_backup(&file_path, &map_data)?;
+            Ok(true)
         }
         None => Ok(false), // User cancelled the dialog
     }
 }
 
-// Command to read imported assets from app data directory
+// Command to list the backup slots available for a saved map
+#[tauri::command]
+async fn list_map_backups(
+    scope: tauri::State<'_, ScopeManager>,
+    path: String,
+) -> Result<Vec<usize>, String> {
+    let allowed_path = scope.check(std::path::Path::new(&path))?;
+    map_backup::list_backups(&allowed_path.to_string_lossy())
+}
+
+// Command to recover a map from one of its rotating backups
+#[tauri::command]
+async fn restore_map_backup(
+    scope: tauri::State<'_, ScopeManager>,
+    path: String,
+    index: usize,
+) -> Result<(), String> {
+    let allowed_path = scope.check(std::path::Path::new(&path))?;
+    map_backup::restore_backup(&allowed_path.to_string_lossy(), index)
+}
+
+// Imported-assets and imported-tiles commands below proxy through the
+// `store` subsystem (store names "assets"/"tiles", single "list" key) so
+// there's one source of truth shared with `assets::import_scanned_assets`,
+// instead of each keeping its own `imported_assets.json`/`tile-store.json`
+// file on the side.
+
+// Command to read imported assets from the "assets" store
 #[tauri::command]
 async fn read_imported_assets(app_handle: tauri::AppHandle) -> Result<String, String> {
-    use tauri::Manager;
-    
-    let app_data_dir = app_handle.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let assets_file = app_data_dir.join("imported_assets.json");
-    
-    // Create directory if it doesn't exist
-    if let Some(parent) = assets_file.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    }
-    
-    match std::fs::read_to_string(&assets_file) {
-        Ok(contents) => Ok(contents),
-        Err(_) => Ok("[]".to_string()), // Return empty array if file doesn't exist
-    }
+    let value = store::get(&app_handle, "assets", "list")?.unwrap_or_else(|| serde_json::json!([]));
+    serde_json::to_string(&value).map_err(|e| format!("Failed to serialize imported assets: {}", e))
 }
 
-// Command to write imported assets to app data directory
+// Command to write imported assets to the "assets" store
 #[tauri::command]
 async fn write_imported_assets(app_handle: tauri::AppHandle, assets_data: String) -> Result<(), String> {
-    use tauri::Manager;
-    
-    let app_data_dir = app_handle.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let assets_file = app_data_dir.join("imported_assets.json");
-    
-    // Create directory if it doesn't exist
-    if let Some(parent) = assets_file.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    }
-    
-    match std::fs::write(&assets_file, assets_data) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Failed to write imported assets: {}", e)),
-    }
+    let value: serde_json::Value = serde_json::from_str(&assets_data)
+        .map_err(|e| format!("Failed to parse imported assets: {}", e))?;
+    store::set(&app_handle, "assets", "list", value)
 }
 
-// Command to clear imported assets file
+// Command to clear the imported-assets store entry
 #[tauri::command]
 async fn clear_imported_assets(app_handle: tauri::AppHandle) -> Result<(), String> {
-    use tauri::Manager;
-    
-    let app_data_dir = app_handle.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let assets_file = app_data_dir.join("imported_assets.json");
-    
-    if assets_file.exists() {
-        match std::fs::remove_file(&assets_file) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to remove imported assets file: {}", e)),
-        }
-    } else {
-        Ok(()) // File doesn't exist, nothing to clear
-    }
+    store::delete(&app_handle, "assets", "list").map(|_| ())
 }
 
-// Command to read imported tiles from app data directory
+// Command to read imported tiles from the "tiles" store
 #[tauri::command]
 async fn read_imported_tiles(app_handle: tauri::AppHandle) -> Result<String, String> {
-    use tauri::Manager;
-    
-    let app_data_dir = app_handle.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let tiles_file = app_data_dir.join("tile-store.json");
-    
-    // Create directory if it doesn't exist
-    if let Some(parent) = tiles_file.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    }
-    
-    match std::fs::read_to_string(&tiles_file) {
-        Ok(contents) => Ok(contents),
-        Err(_) => Ok("{\"tiles\":[],\"version\":2}".to_string()), // Return empty store structure if file doesn't exist
-    }
+    let value = store::get(&app_handle, "tiles", "list")?
+        .unwrap_or_else(|| serde_json::json!({"tiles": [], "version": 2}));
+    serde_json::to_string(&value).map_err(|e| format!("Failed to serialize imported tiles: {}", e))
 }
 
-// Command to write imported tiles to app data directory
+// Command to write imported tiles to the "tiles" store
 #[tauri::command]
 async fn write_imported_tiles(app_handle: tauri::AppHandle, tiles_data: String) -> Result<(), String> {
-    use tauri::Manager;
-    
-    let app_data_dir = app_handle.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let tiles_file = app_data_dir.join("tile-store.json");
-    
-    // Create directory if it doesn't exist
-    if let Some(parent) = tiles_file.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
-    }
-    
-    match std::fs::write(&tiles_file, tiles_data) {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Failed to write imported tiles: {}", e)),
-    }
+    let value: serde_json::Value = serde_json::from_str(&tiles_data)
+        .map_err(|e| format!("Failed to parse imported tiles: {}", e))?;
+    store::set(&app_handle, "tiles", "list", value)
 }
 
-// Command to clear imported tiles file
+// Command to clear the imported-tiles store entry
 #[tauri::command]
 async fn clear_imported_tiles(app_handle: tauri::AppHandle) -> Result<(), String> {
-    use tauri::Manager;
-    
-    let app_data_dir = app_handle.path().app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-    
-    let tiles_file = app_data_dir.join("tile-store.json");
-    
-    if tiles_file.exists() {
-        match std::fs::remove_file(&tiles_file) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(format!("Failed to remove imported tiles file: {}", e)),
-        }
-    } else {
-        Ok(()) // File doesn't exist, nothing to clear
-    }
+    store::delete(&app_handle, "tiles", "list").map(|_| ())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -222,21 +243,42 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(store::StoreManager::default())
+        .manage(ScopeManager::default())
         .invoke_handler(tauri::generate_handler![
             open_file_dialog,
             save_file_dialog,
+            open_files_dialog,
             read_file,
             write_file,
             load_map,
+            load_maps,
             save_map,
             read_imported_assets,
             write_imported_assets,
             clear_imported_assets,
             read_imported_tiles,
             write_imported_tiles,
-            clear_imported_tiles
+            clear_imported_tiles,
+            store::store_get,
+            store::store_set,
+            store::store_delete,
+            store::store_keys,
+            assets::scan_assets_dir,
+            assets::import_scanned_assets,
+            list_map_backups,
+            restore_map_backup,
+            binary::read_file_base64,
+            binary::write_file_base64,
+            scope::revoke_path_scope
         ])
         .setup(|app| {
+            use tauri::Manager;
+
+            let app_data_dir = app.path().app_data_dir()?;
+            std::fs::create_dir_all(&app_data_dir)?;
+            app.state::<ScopeManager>().grant(&app_data_dir)?;
+
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
@@ -246,6 +288,13 @@ pub fn run() {
             }
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Stores only flush on a debounce; flush synchronously on exit
+            // so a clean quit within that window can't drop a mutation.
+            if let tauri::RunEvent::Exit = event {
+                store::flush_all(app_handle);
+            }
+        });
 }
@@ -0,0 +1,84 @@
+// Crash-safe map saves with rotating backups.
+//
+// A save never overwrites the existing file in place: the current file is
+// rotated into numbered `.bak` slots first, then the new contents go to a
+// sibling temp file that is renamed into place only once it's fully
+// written. That way a crash mid-write never leaves a truncated file at the
+// map's path: the previous version is already safe in `.bak.1`, and the path
+// holds either nothing or the fully-written new one. `restore_backup` gives
+// the user an undo-of-last-resort if the new save turns out to be bad.
+
+use std::path::{Path, PathBuf};
+
+const MAX_BACKUPS: usize = 5;
+
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    PathBuf::from(format!("{}.bak.{}", path.display(), index))
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.tmp", path.display()))
+}
+
+fn rotate_backups(path: &Path) -> Result<(), String> {
+    let oldest = backup_path(path, MAX_BACKUPS);
+    if oldest.exists() {
+        std::fs::remove_file(&oldest)
+            .map_err(|e| format!("Failed to discard old backup {}: {}", oldest.display(), e))?;
+    }
+
+    for index in (1..MAX_BACKUPS).rev() {
+        let from = backup_path(path, index);
+        if from.exists() {
+            let to = backup_path(path, index + 1);
+            std::fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to rotate backup {}: {}", from.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
+
+// Writes `contents` to `path_str`, rotating any existing file into the
+// backup slots and going through a temp file + rename so the write is
+// atomic from the filesystem's point of view.
+pub fn atomic_write_with_backup(path_str: &str, contents: &str) -> Result<(), String> {
+    let path = Path::new(path_str);
+
+    if path.exists() {
+        rotate_backups(path)?;
+        std::fs::rename(path, backup_path(path, 1))
+            .map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    }
+
+    let tmp = tmp_path(path);
+    std::fs::write(&tmp, contents)
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    std::fs::rename(&tmp, path)
+        .map_err(|e| format!("Failed to finalize {}: {}", path.display(), e))?;
+
+    Ok(())
+}
+
+// Returns the backup slot indices that currently exist for `path_str`,
+// ordered newest (1) to oldest.
+pub fn list_backups(path_str: &str) -> Result<Vec<usize>, String> {
+    let path = Path::new(path_str);
+    let mut found = Vec::new();
+    for index in 1..=MAX_BACKUPS {
+        if backup_path(path, index).exists() {
+            found.push(index);
+        }
+    }
+    Ok(found)
+}
+
+// Restores backup slot `index` over `path_str`, itself going through
+// `atomic_write_with_backup` so the file being replaced is backed up too.
+pub fn restore_backup(path_str: &str, index: usize) -> Result<(), String> {
+    let path = Path::new(path_str);
+    let backup = backup_path(path, index);
+    let contents = std::fs::read_to_string(&backup)
+        .map_err(|e| format!("Failed to read backup {}: {}", backup.display(), e))?;
+    atomic_write_with_backup(path_str, &contents)
+}
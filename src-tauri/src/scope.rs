@@ -0,0 +1,132 @@
+// Scoped filesystem access.
+//
+// `read_file`/`write_file` used to accept any absolute path from the
+// webview, which hands the frontend read/write access to the whole disk.
+// Instead the backend now holds an allowlist of directories the user has
+// explicitly opted into (the app data dir, plus whatever folder they picked
+// through a dialog) and every file command has to resolve inside one of
+// them or it's rejected. Modeled on Tauri's own ACL/scope model, except the
+// scopes here are granted dynamically as the user interacts with dialogs
+// rather than declared statically in config.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct ScopeManager {
+    allowed: Mutex<Vec<PathBuf>>,
+}
+
+impl ScopeManager {
+    fn containing_dir(path: &Path) -> Result<PathBuf, String> {
+        if path.is_dir() {
+            Ok(path.to_path_buf())
+        } else {
+            path.parent()
+                .map(|parent| parent.to_path_buf())
+                .ok_or_else(|| format!("Invalid path: {}", path.display()))
+        }
+    }
+
+    // Adds `path` (or its containing directory, if it's a file) to the
+    // allowlist.
+    pub fn grant(&self, path: &Path) -> Result<(), String> {
+        let dir = Self::containing_dir(path)?;
+        let canonical = dir
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve path: {}", e))?;
+
+        let mut allowed = self.allowed.lock().unwrap();
+        if !allowed.contains(&canonical) {
+            allowed.push(canonical);
+        }
+        Ok(())
+    }
+
+    // Removes `path`'s containing directory from the allowlist, if present.
+    pub fn revoke(&self, path: &Path) {
+        let dir = Self::containing_dir(path).unwrap_or_else(|_| path.to_path_buf());
+        let canonical = dir.canonicalize().unwrap_or(dir);
+
+        let mut allowed = self.allowed.lock().unwrap();
+        allowed.retain(|scope| scope != &canonical);
+    }
+
+    // Resolves `path` and rejects it unless it falls inside a granted
+    // scope. If `path` already exists, the whole path is canonicalized so a
+    // symlink sitting at the final component (e.g. dropped by an imported
+    // archive) is resolved and checked rather than silently followed after
+    // the check passes. Only when the leaf genuinely doesn't exist yet (e.g.
+    // a new save target) do we fall back to resolving just the parent
+    // directory and joining the raw file name onto it.
+    pub fn check(&self, path: &Path) -> Result<PathBuf, String> {
+        // `symlink_metadata` rather than `exists`, which reports a dangling
+        // symlink as missing and would let it take the fallback below.
+        if path.symlink_metadata().is_ok() {
+            return self.check_existing(path);
+        }
+
+        let parent = path
+            .parent()
+            .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+        let canonical_parent = parent
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve path: {}", e))?;
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+        let resolved = canonical_parent.join(file_name);
+
+        self.check_contains(&resolved)
+    }
+
+    // Resolves `path` as a directory that must already exist and rejects it
+    // unless it falls inside a granted scope. Used by commands that walk or
+    // operate on a whole directory (e.g. the asset folder scanner) rather
+    // than a single file.
+    pub fn check_dir(&self, path: &Path) -> Result<PathBuf, String> {
+        let resolved = self.check_existing(path)?;
+        if !resolved.is_dir() {
+            return Err(format!("Not a directory: {}", resolved.display()));
+        }
+        Ok(resolved)
+    }
+
+    // Canonicalizes a path that must already exist (file or directory) and
+    // rejects it unless it falls inside a granted scope.
+    fn check_existing(&self, path: &Path) -> Result<PathBuf, String> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve path: {}", e))?;
+        self.check_contains(&canonical)
+    }
+
+    fn check_contains(&self, resolved: &Path) -> Result<PathBuf, String> {
+        let allowed = self.allowed.lock().unwrap();
+        if allowed.iter().any(|scope| resolved.starts_with(scope)) {
+            Ok(resolved.to_path_buf())
+        } else {
+            Err(format!(
+                "Access to '{}' is not permitted: path is outside any granted scope",
+                resolved.display()
+            ))
+        }
+    }
+}
+
+// Note: there is deliberately no `grant_path_scope` command. Granting a
+// scope is only ever a side effect of `open_file_dialog`/`save_file_dialog`/
+// `open_files_dialog` succeeding (a real, user-driven file pick), never a
+// standalone invokable — otherwise any webview code could call it with an
+// arbitrary path (e.g. `/`) and forge the same access those dialogs exist to
+// gate.
+
+// Command to revoke a previously granted scope
+#[tauri::command]
+pub async fn revoke_path_scope(
+    manager: tauri::State<'_, ScopeManager>,
+    path: String,
+) -> Result<(), String> {
+    manager.revoke(Path::new(&path));
+    Ok(())
+}
@@ -0,0 +1,251 @@
+// In-memory key/value stores with debounced, atomic disk flushes.
+//
+// Modeled after tauri-plugin-store: each named store ("assets", "tiles", ...)
+// is loaded into memory on first access and mutations are applied in-place,
+// so callers never have to read-modify-write the whole JSON document
+// themselves. Writes to disk are coalesced behind a short debounce so a burst
+// of edits only costs one flush, and the flush itself goes through a temp
+// file + rename so a crash mid-write can't corrupt the store.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager};
+
+const FLUSH_DEBOUNCE_MS: u64 = 200;
+const STORE_CHANGE_EVENT: &str = "store://change";
+
+struct StoreState {
+    data: HashMap<String, Value>,
+    generation: AtomicU64,
+}
+
+impl StoreState {
+    fn new(data: HashMap<String, Value>) -> Self {
+        Self {
+            data,
+            generation: AtomicU64::new(0),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct StoreManager {
+    stores: Mutex<HashMap<String, StoreState>>,
+}
+
+#[derive(Clone, Serialize)]
+struct StoreChangePayload<'a> {
+    store: &'a str,
+    key: &'a str,
+}
+
+// Store names come straight from the webview (`store_get`/`store_set`/etc.
+// take `store: String`), so they have to be validated as a single safe path
+// component before being joined onto the app data dir — otherwise an
+// absolute name replaces the base entirely (`PathBuf::join` semantics) or a
+// `..` component escapes it, turning the store into an arbitrary-file-write
+// primitive.
+fn validate_store_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid store name '{}'", name))
+    }
+}
+
+fn store_path(app_handle: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    validate_store_name(name)?;
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    Ok(app_data_dir.join(format!("{}.json", name)))
+}
+
+fn load_store(app_handle: &AppHandle, name: &str) -> Result<HashMap<String, Value>, String> {
+    let path = store_path(app_handle, name)?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse store '{}': {}", name, e)),
+        Err(_) => Ok(HashMap::new()),
+    }
+}
+
+fn ensure_store<'a>(
+    app_handle: &AppHandle,
+    stores: &'a mut HashMap<String, StoreState>,
+    name: &str,
+) -> Result<&'a mut StoreState, String> {
+    if !stores.contains_key(name) {
+        let data = load_store(app_handle, name)?;
+        stores.insert(name.to_string(), StoreState::new(data));
+    }
+    Ok(stores.get_mut(name).unwrap())
+}
+
+fn flush_store(app_handle: &AppHandle, name: &str) -> Result<(), String> {
+    let path = store_path(app_handle, name)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+
+    let json = {
+        let manager = app_handle.state::<StoreManager>();
+        let stores = manager.stores.lock().unwrap();
+        let state = stores
+            .get(name)
+            .ok_or_else(|| format!("Unknown store '{}'", name))?;
+        serde_json::to_string(&state.data)
+            .map_err(|e| format!("Failed to serialize store '{}': {}", name, e))?
+    };
+
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json)
+        .map_err(|e| format!("Failed to write store '{}': {}", name, e))?;
+    std::fs::rename(&tmp_path, &path)
+        .map_err(|e| format!("Failed to finalize store '{}': {}", name, e))?;
+    Ok(())
+}
+
+// Schedules a flush after `FLUSH_DEBOUNCE_MS`, skipping it if another
+// mutation has bumped the store's generation in the meantime so rapid edits
+// coalesce into a single write.
+fn schedule_flush(app_handle: AppHandle, name: String, generation: u64) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(FLUSH_DEBOUNCE_MS)).await;
+
+        let is_current = {
+            let manager = app_handle.state::<StoreManager>();
+            let stores = manager.stores.lock().unwrap();
+            stores
+                .get(&name)
+                .map(|state| state.generation.load(Ordering::SeqCst) == generation)
+                .unwrap_or(false)
+        };
+
+        if is_current {
+            if let Err(e) = flush_store(&app_handle, &name) {
+                log::error!("Failed to flush store '{}': {}", name, e);
+            }
+        }
+    });
+}
+
+fn emit_change(app_handle: &AppHandle, store: &str, key: &str) {
+    let _ = app_handle.emit(STORE_CHANGE_EVENT, StoreChangePayload { store, key });
+}
+
+// Best-effort synchronous flush of every loaded store, bypassing the
+// debounce. Called on app exit so a clean quit within the debounce window
+// doesn't silently drop the most recent mutation.
+pub fn flush_all(app_handle: &AppHandle) {
+    let manager = app_handle.state::<StoreManager>();
+    let names: Vec<String> = {
+        let stores = manager.stores.lock().unwrap();
+        stores.keys().cloned().collect()
+    };
+    for name in names {
+        if let Err(e) = flush_store(app_handle, &name) {
+            log::error!("Failed to flush store '{}' on exit: {}", name, e);
+        }
+    }
+}
+
+// Looks up `key` in `store`. Usable directly by other command modules, same
+// as `set`.
+pub fn get(app_handle: &AppHandle, store: &str, key: &str) -> Result<Option<Value>, String> {
+    let manager = app_handle.state::<StoreManager>();
+    let mut stores = manager.stores.lock().unwrap();
+    let state = ensure_store(app_handle, &mut stores, store)?;
+    Ok(state.data.get(key).cloned())
+}
+
+#[tauri::command]
+pub async fn store_get(
+    app_handle: AppHandle,
+    store: String,
+    key: String,
+) -> Result<Option<Value>, String> {
+    get(&app_handle, &store, &key)
+}
+
+// Inserts `key` into `store`, scheduling a debounced flush and notifying the
+// frontend. Usable directly by other command modules (e.g. asset import)
+// that need to populate a store without round-tripping through the
+// `store_set` command.
+pub fn set(app_handle: &AppHandle, store: &str, key: &str, value: Value) -> Result<(), String> {
+    let generation = {
+        let manager = app_handle.state::<StoreManager>();
+        let mut stores = manager.stores.lock().unwrap();
+        let state = ensure_store(app_handle, &mut stores, store)?;
+        state.data.insert(key.to_string(), value);
+        state.generation.fetch_add(1, Ordering::SeqCst) + 1
+    };
+
+    schedule_flush(app_handle.clone(), store.to_string(), generation);
+    emit_change(app_handle, store, key);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn store_set(
+    app_handle: AppHandle,
+    store: String,
+    key: String,
+    value: Value,
+) -> Result<(), String> {
+    set(&app_handle, &store, &key, value)
+}
+
+// Removes `key` from `store`, scheduling a debounced flush if anything was
+// actually removed. Usable directly by other command modules, same as
+// `set`.
+pub fn delete(app_handle: &AppHandle, store: &str, key: &str) -> Result<bool, String> {
+    let (removed, generation) = {
+        let manager = app_handle.state::<StoreManager>();
+        let mut stores = manager.stores.lock().unwrap();
+        let state = ensure_store(app_handle, &mut stores, store)?;
+        let removed = state.data.remove(key).is_some();
+        // Only a real removal bumps the generation; bumping on a no-op would
+        // cancel a pending flush without scheduling a new one.
+        let generation = if removed {
+            state.generation.fetch_add(1, Ordering::SeqCst) + 1
+        } else {
+            state.generation.load(Ordering::SeqCst)
+        };
+        (removed, generation)
+    };
+
+    if removed {
+        schedule_flush(app_handle.clone(), store.to_string(), generation);
+        emit_change(app_handle, store, key);
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn store_delete(app_handle: AppHandle, store: String, key: String) -> Result<bool, String> {
+    delete(&app_handle, &store, &key)
+}
+
+#[tauri::command]
+pub async fn store_keys(
+    app_handle: AppHandle,
+    manager: tauri::State<'_, StoreManager>,
+    store: String,
+) -> Result<Vec<String>, String> {
+    let mut stores = manager.stores.lock().unwrap();
+    let state = ensure_store(&app_handle, &mut stores, &store)?;
+    Ok(state.data.keys().cloned().collect())
+}